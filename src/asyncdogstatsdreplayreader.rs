@@ -0,0 +1,136 @@
+#![cfg(feature = "async")]
+
+use bytes::BytesMut;
+use prost::Message;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::dogstatsdreplayreader::dogstatsd::unix::UnixDogstatsdMsg;
+use crate::dogstatsdreplayreader::{DogStatsDReplayReaderError, ReplayRecord, REPLAY_HEADER};
+
+/// Streaming counterpart to
+/// [`DogStatsDReplayReader`](crate::dogstatsdreplayreader::DogStatsDReplayReader)
+/// for sources that can't be buffered into memory up front, such as a
+/// replay being pulled from a slow or remote store. Built directly on
+/// `tokio::io::AsyncRead` so frames are decoded as bytes arrive rather than
+/// requiring the whole capture up front.
+pub struct AsyncDogStatsDReplayReader<R> {
+    inner: R,
+    header_checked: bool,
+    frame_index: usize,
+    lossy: bool,
+}
+
+impl<R> AsyncDogStatsDReplayReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    pub fn new(inner: R) -> Self {
+        AsyncDogStatsDReplayReader {
+            inner,
+            header_checked: false,
+            frame_index: 0,
+            lossy: false,
+        }
+    }
+
+    /// Enables lossy decoding: a frame with invalid UTF-8 is substituted
+    /// with replacement characters and the stream continues, instead of
+    /// `read_record` returning `Err`.
+    pub fn with_lossy(mut self, lossy: bool) -> Self {
+        self.lossy = lossy;
+        self
+    }
+
+    async fn check_header(&mut self) -> Result<(), DogStatsDReplayReaderError> {
+        if self.header_checked {
+            return Ok(());
+        }
+
+        let mut header = [0u8; REPLAY_HEADER.len()];
+        self.inner
+            .read_exact(&mut header)
+            .await
+            .map_err(|_| DogStatsDReplayReaderError::NotAReplayFile)?;
+
+        if header != REPLAY_HEADER {
+            return Err(DogStatsDReplayReaderError::NotAReplayFile);
+        }
+
+        self.header_checked = true;
+        Ok(())
+    }
+
+    /// Reads the next record, returning `Ok(None)` once the replay is
+    /// exhausted. Only the current frame is ever held in memory.
+    pub async fn read_record(
+        &mut self,
+    ) -> Result<Option<ReplayRecord>, DogStatsDReplayReaderError> {
+        loop {
+            self.check_header().await?;
+
+            let mut len_buf = [0u8; 4];
+            if self.inner.read_exact(&mut len_buf).await.is_err() {
+                return Ok(None);
+            }
+
+            let frame_len = u32::from_le_bytes(len_buf) as usize;
+            if frame_len == 0 {
+                return Ok(None);
+            }
+
+            let mut frame = BytesMut::zeroed(frame_len);
+            self.inner
+                .read_exact(&mut frame)
+                .await
+                .map_err(|_| DogStatsDReplayReaderError::NotAReplayFile)?;
+
+            let msg = UnixDogstatsdMsg::decode(frame.freeze())
+                .map_err(|_| DogStatsDReplayReaderError::NotAReplayFile)?;
+
+            let frame_index = self.frame_index;
+            self.frame_index += 1;
+
+            let lines: Vec<String> = match std::str::from_utf8(&msg.payload) {
+                Ok(v) => v.lines().map(String::from).collect(),
+                Err(_) if self.lossy => String::from_utf8_lossy(&msg.payload)
+                    .lines()
+                    .map(String::from)
+                    .collect(),
+                Err(e) => {
+                    return Err(DogStatsDReplayReaderError::InvalidUtf8Sequence {
+                        payload: msg.payload,
+                        valid_up_to: e.valid_up_to(),
+                        frame_index,
+                    })
+                }
+            };
+
+            if lines.is_empty() {
+                continue;
+            }
+
+            return Ok(Some(ReplayRecord {
+                timestamp: msg.timestamp,
+                pid: msg.pid,
+                ancillary: msg.ancillary,
+                lines,
+            }));
+        }
+    }
+
+    /// Turns this reader into a [`Stream`](futures_core::Stream) of decoded
+    /// records, so a remote/slow replay can be consumed with `StreamExt`
+    /// combinators instead of a manual `read_record` loop.
+    pub fn into_stream(
+        mut self,
+    ) -> impl futures_core::Stream<Item = Result<ReplayRecord, DogStatsDReplayReaderError>>
+    where
+        R: 'static,
+    {
+        async_stream::try_stream! {
+            while let Some(record) = self.read_record().await? {
+                yield record;
+            }
+        }
+    }
+}
@@ -1,24 +1,58 @@
 use std::env;
-use std::fs::File;
-use std::io::Error;
+use std::fs;
+use std::io::Write;
+use std::process;
 
-use dogstatsd_utils::dogstatsdreplay::DogStatsDReplay;
+use bytes::Bytes;
 
-fn main() -> Result<(), Error> {
+use dogstatsd_utils::dogstatsdmsgstats::analyze;
+use dogstatsd_utils::dogstatsdreplayreader::DogStatsDReplayReader;
+
+fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage: {} <file_path>", args[0]);
-        std::process::exit(1);
+    if args.len() < 2 || args.len() > 3 {
+        eprintln!("Usage: {} <file_path> [--stats]", args[0]);
+        process::exit(1);
     }
+
     let file_path = &args[1];
-    let mut file = File::open(file_path)?;
+    let stats_mode = args.get(2).map(|a| a == "--stats").unwrap_or(false);
+
+    let buf = fs::read(file_path).unwrap_or_else(|e| {
+        eprintln!("Failed to read {}: {}", file_path, e);
+        process::exit(1);
+    });
 
-    let mut replay = DogStatsDReplay::try_from(&mut file)?;
+    let mut replay = DogStatsDReplayReader::new(Bytes::from(buf)).unwrap_or_else(|e| {
+        eprintln!("Failed to parse replay: {}", e);
+        process::exit(1);
+    });
+
+    if stats_mode {
+        let stats = analyze(&mut replay).unwrap_or_else(|e| {
+            eprintln!("Failed to analyze replay: {}", e);
+            process::exit(1);
+        });
+        println!("{}", stats);
+        return;
+    }
 
     let destination_file_path = file_path.to_owned() + ".txt";
+    let mut out = fs::File::create(&destination_file_path).unwrap_or_else(|e| {
+        eprintln!("Failed to create {}: {}", destination_file_path, e);
+        process::exit(1);
+    });
 
-    replay.write_to(&destination_file_path)?;
+    for line in replay {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("Failed to read replay: {}", e);
+            process::exit(1);
+        });
+        writeln!(out, "{}", line).unwrap_or_else(|e| {
+            eprintln!("Failed to write {}: {}", destination_file_path, e);
+            process::exit(1);
+        });
+    }
 
     println!("Done! Result is in {}", destination_file_path);
-    Ok(())
 }
@@ -0,0 +1,52 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process;
+
+use dogstatsd_utils::dogstatsdreplaywriter::DogStatsDReplayWriter;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        eprintln!(
+            "Usage: {} <output_replay.bin>  (reads raw DogStatsD lines from stdin)",
+            args[0]
+        );
+        process::exit(1);
+    }
+
+    let pid = process::id() as i32;
+    let out_file = File::create(&args[1]).unwrap_or_else(|e| {
+        eprintln!("Failed to create {}: {}", args[1], e);
+        process::exit(1);
+    });
+
+    let mut writer = DogStatsDReplayWriter::new(out_file);
+    let stdin = io::stdin();
+    let reader = BufReader::new(stdin.lock());
+
+    let mut timestamp = 0i64;
+    for line in reader.lines() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("Failed to read stdin: {}", e);
+            process::exit(1);
+        });
+
+        writer.write_line(&line, timestamp, pid).unwrap_or_else(|e| {
+            eprintln!("Failed to encode line: {}", e);
+            process::exit(1);
+        });
+
+        // Fixture lines have no real capture time, so synthesize one
+        // millisecond of spacing between each so a replay of the output
+        // still has a meaningful, monotonic cadence.
+        timestamp += 1_000_000;
+    }
+
+    writer.finish().unwrap_or_else(|e| {
+        eprintln!("Failed to finish replay: {}", e);
+        process::exit(1);
+    });
+
+    println!("Done! Result is in {}", args[1]);
+}
@@ -0,0 +1,87 @@
+use std::env;
+use std::fs;
+use std::net::UdpSocket;
+use std::os::unix::net::UnixDatagram;
+use std::process;
+
+use bytes::Bytes;
+
+use dogstatsd_utils::dogstatsdreplaysender::{DogStatsDReplaySender, ReplayDestination};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 3 {
+        eprintln!(
+            "Usage: {} <replay_file> <target> [--uds] [--rate <multiplier>] [--loop]",
+            args[0]
+        );
+        eprintln!("  <target> is a UDP socket address, or with --uds, a Unix datagram socket path");
+        process::exit(1);
+    }
+
+    let file_path = &args[1];
+    let target = &args[2];
+
+    let mut rate = 1.0;
+    let mut looping = false;
+    let mut use_uds = false;
+    let mut i = 3;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--rate" => {
+                i += 1;
+                rate = args
+                    .get(i)
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .unwrap_or_else(|| {
+                        eprintln!("--rate requires a numeric multiplier");
+                        process::exit(1);
+                    });
+            }
+            "--loop" => looping = true,
+            "--uds" => use_uds = true,
+            other => {
+                eprintln!("Unrecognized argument: {}", other);
+                process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let buf = fs::read(file_path).unwrap_or_else(|e| {
+        eprintln!("Failed to read {}: {}", file_path, e);
+        process::exit(1);
+    });
+
+    let destination = if use_uds {
+        let socket = UnixDatagram::unbound().unwrap_or_else(|e| {
+            eprintln!("Failed to create Unix datagram socket: {}", e);
+            process::exit(1);
+        });
+        socket.connect(target).unwrap_or_else(|e| {
+            eprintln!("Failed to connect to {}: {}", target, e);
+            process::exit(1);
+        });
+        ReplayDestination::UnixDatagram(socket)
+    } else {
+        let socket = UdpSocket::bind("0.0.0.0:0").unwrap_or_else(|e| {
+            eprintln!("Failed to bind UDP socket: {}", e);
+            process::exit(1);
+        });
+        socket.connect(target).unwrap_or_else(|e| {
+            eprintln!("Failed to connect to {}: {}", target, e);
+            process::exit(1);
+        });
+        ReplayDestination::Udp(socket)
+    };
+
+    let mut sender = DogStatsDReplaySender::new(Bytes::from(buf), destination, rate)
+        .with_loop(looping);
+
+    if let Err(e) = sender.run() {
+        eprintln!("Replay send failed: {}", e);
+        process::exit(1);
+    }
+
+    println!("Done replaying {} to {}", file_path, target);
+}
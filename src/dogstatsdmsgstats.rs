@@ -0,0 +1,173 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::dogstatsdreplayreader::{DogStatsDReplayReader, DogStatsDReplayReaderError, ReplayRecord};
+use crate::metricline::{self, MetricType};
+
+/// Aggregate statistics describing a decoded replay: how many metrics of
+/// each type it contains, how diverse its names and tags are, and the
+/// shape of its sample rates and payload sizes. Meant to let a user
+/// characterize a capture without writing out the full `.txt`.
+#[derive(Debug, Default, Serialize)]
+pub struct ReplayStats {
+    pub total_lines: u64,
+    pub unparsed_lines: u64,
+    pub count_by_type: HashMap<String, u64>,
+    pub distinct_metric_names: u64,
+    pub distinct_tag_keys: u64,
+    pub distinct_tag_values: u64,
+    pub sample_rate_histogram: HashMap<String, u64>,
+    /// Bucketed by the size of each decoded message's payload (all of its
+    /// metric lines rejoined), not by individual line length.
+    pub payload_size_histogram: HashMap<String, u64>,
+
+    #[serde(skip)]
+    metric_names: HashSet<String>,
+    #[serde(skip)]
+    tag_keys: HashSet<String>,
+    #[serde(skip)]
+    tag_values: HashSet<String>,
+}
+
+impl ReplayStats {
+    /// Records one decoded `dogstatsd.unix` message: its payload size goes
+    /// into `payload_size_histogram` once for the whole message, while each
+    /// of its metric lines is parsed individually for the per-type and
+    /// cardinality stats.
+    fn record_message(&mut self, record: &ReplayRecord) {
+        let payload_len = record.lines.join("\n").len();
+        *self
+            .payload_size_histogram
+            .entry(size_bucket(payload_len))
+            .or_insert(0) += 1;
+
+        for line in &record.lines {
+            self.record_line(line);
+        }
+    }
+
+    fn record_line(&mut self, line: &str) {
+        self.total_lines += 1;
+
+        let Some(parsed) = metricline::parse_line(line) else {
+            self.unparsed_lines += 1;
+            return;
+        };
+
+        *self
+            .count_by_type
+            .entry(type_label(parsed.metric_type).to_string())
+            .or_insert(0) += 1;
+
+        self.metric_names.insert(parsed.name);
+
+        for (key, value) in parsed.tags {
+            self.tag_keys.insert(key);
+            if let Some(value) = value {
+                self.tag_values.insert(value);
+            }
+        }
+
+        if let Some(rate) = parsed.sample_rate {
+            *self
+                .sample_rate_histogram
+                .entry(format!("{:.2}", rate))
+                .or_insert(0) += 1;
+        }
+    }
+
+    fn finalize(mut self) -> Self {
+        self.distinct_metric_names = self.metric_names.len() as u64;
+        self.distinct_tag_keys = self.tag_keys.len() as u64;
+        self.distinct_tag_values = self.tag_values.len() as u64;
+        self
+    }
+}
+
+fn type_label(metric_type: MetricType) -> &'static str {
+    match metric_type {
+        MetricType::Counter => "counter",
+        MetricType::Gauge => "gauge",
+        MetricType::Histogram => "histogram",
+        MetricType::Distribution => "distribution",
+        MetricType::Set => "set",
+        MetricType::Timer => "timer",
+    }
+}
+
+/// Buckets payload sizes by power-of-two ranges so the histogram stays
+/// small regardless of how many distinct sizes appear in the capture.
+fn size_bucket(size: usize) -> String {
+    if size == 0 {
+        return "0".to_string();
+    }
+
+    let upper = size.next_power_of_two();
+    let lower = upper / 2 + 1;
+    format!("{}-{}", lower, upper)
+}
+
+impl fmt::Display for ReplayStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => write!(f, "{}", json),
+            Err(e) => write!(f, "<failed to serialize ReplayStats: {}>", e),
+        }
+    }
+}
+
+/// Consumes the entire replay and reports aggregate statistics: count per
+/// metric type, name/tag cardinality, sample-rate distribution, and
+/// payload-size histogram.
+pub fn analyze(reader: &mut DogStatsDReplayReader) -> Result<ReplayStats, DogStatsDReplayReaderError> {
+    let mut stats = ReplayStats::default();
+
+    while let Some(record) = reader.read_record()? {
+        stats.record_message(&record);
+    }
+
+    Ok(stats.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[test]
+    fn analyze_counts_metric_types_and_cardinality() {
+        const ONE_MSG_THREE_LINES: &[u8] = &[
+            0xd4, 0x74, 0xd0, 0x60, 0xf3, 0xff, 0x00, 0x00, 0xa9, 0x00, 0x00, 0x00, 0x08, 0xa7,
+            0xe3, 0x97, 0xff, 0xaf, 0xbb, 0x88, 0xbf, 0x17, 0x10, 0x99, 0x01, 0x1a, 0x99, 0x01,
+            0x73, 0x74, 0x61, 0x74, 0x73, 0x64, 0x2e, 0x6f, 0x74, 0x68, 0x65, 0x72, 0x2e, 0x6d,
+            0x65, 0x74, 0x72, 0x69, 0x63, 0x3a, 0x33, 0x7c, 0x63, 0x7c, 0x40, 0x31, 0x2e, 0x30,
+            0x30, 0x30, 0x30, 0x30, 0x30, 0x7c, 0x23, 0x65, 0x6e, 0x76, 0x69, 0x72, 0x6f, 0x6e,
+            0x6d, 0x65, 0x6e, 0x74, 0x3a, 0x64, 0x65, 0x76, 0x0a, 0x73, 0x74, 0x61, 0x74, 0x73,
+            0x64, 0x2e, 0x6f, 0x74, 0x68, 0x65, 0x72, 0x2e, 0x6d, 0x65, 0x74, 0x72, 0x69, 0x63,
+            0x3a, 0x38, 0x7c, 0x63, 0x7c, 0x40, 0x31, 0x2e, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+            0x7c, 0x23, 0x65, 0x6e, 0x76, 0x69, 0x72, 0x6f, 0x6e, 0x6d, 0x65, 0x6e, 0x74, 0x3a,
+            0x64, 0x65, 0x76, 0x0a, 0x73, 0x74, 0x61, 0x74, 0x73, 0x64, 0x2e, 0x6f, 0x74, 0x68,
+            0x65, 0x72, 0x2e, 0x6d, 0x65, 0x74, 0x72, 0x69, 0x63, 0x3a, 0x37, 0x7c, 0x63, 0x7c,
+            0x40, 0x31, 0x2e, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x7c, 0x23, 0x65, 0x6e, 0x76,
+            0x69, 0x72, 0x6f, 0x6e, 0x6d, 0x65, 0x6e, 0x74, 0x3a, 0x64, 0x65, 0x76, 0x0a, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let mut reader = DogStatsDReplayReader::new(Bytes::from(ONE_MSG_THREE_LINES)).unwrap();
+        let stats = analyze(&mut reader).unwrap();
+
+        assert_eq!(stats.total_lines, 3);
+        assert_eq!(stats.unparsed_lines, 0);
+        assert_eq!(stats.count_by_type.get("counter"), Some(&3));
+        assert_eq!(stats.distinct_metric_names, 1);
+        assert_eq!(stats.distinct_tag_keys, 1);
+        assert_eq!(stats.distinct_tag_values, 1);
+
+        // One message with 3 lines should produce a single payload-size
+        // bucket entry, not one per line.
+        let total_payload_samples: u64 = stats.payload_size_histogram.values().sum();
+        assert_eq!(total_payload_samples, 1);
+    }
+}
@@ -5,6 +5,12 @@ use bytes::Bytes;
 
 use crate::replay::{ReplayReader, ReplayReaderError};
 
+/// 8-byte magic + version marker that prefixes every `.bin` replay capture.
+/// This is the same header `ReplayReader::new` validates; the writer and
+/// async reader reference this constant rather than redefining it so the
+/// three stay in sync if the format ever revs.
+pub(crate) const REPLAY_HEADER: [u8; 8] = [0xd4, 0x74, 0xd0, 0x60, 0xf3, 0xff, 0x00, 0x00];
+
 pub mod dogstatsd {
     pub mod unix {
         include!(concat!(env!("OUT_DIR"), "/dogstatsd.unix.rs"));
@@ -17,41 +23,106 @@ pub enum DogStatsDReplayReaderError {
     NotAReplayFile,
     #[error("Unsupported replay version")]
     UnsupportedReplayVersion,
-    #[error("Invalid UTF-8 sequence found in payload of msg")]
-    InvalidUtf8Sequence,
+    #[error("Invalid UTF-8 sequence found in payload of msg {frame_index} at offset {valid_up_to}")]
+    InvalidUtf8Sequence {
+        /// The raw payload bytes that failed to decode, so the caller can
+        /// inspect or re-attempt the decode themselves.
+        payload: Bytes,
+        /// Byte offset of the first invalid sequence, from `Utf8Error::valid_up_to`.
+        valid_up_to: usize,
+        /// Index (0-based) of the frame within the replay that this payload
+        /// came from.
+        frame_index: usize,
+    },
+}
+
+/// A single decoded `dogstatsd.unix` message, split into its metric lines but
+/// still carrying the metadata that `read_msg` otherwise throws away.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayRecord {
+    pub timestamp: i64,
+    pub pid: i32,
+    pub ancillary: Bytes,
+    pub lines: Vec<String>,
 }
 
 pub struct DogStatsDReplayReader {
     replay_msg_reader: ReplayReader,
     current_messages: VecDeque<String>,
+    frame_index: usize,
+    /// When set, a payload that fails to decode as UTF-8 is salvaged with
+    /// `String::from_utf8_lossy` instead of aborting the read.
+    lossy: bool,
+    /// Set once the `Iterator` impl has yielded an `Err`, so iteration
+    /// fuses instead of re-attempting a read past the failure.
+    errored: bool,
 }
 
 impl DogStatsDReplayReader {
+    /// Enables lossy decoding: a frame with invalid UTF-8 is substituted
+    /// with replacement characters and the stream continues, instead of
+    /// `read_record`/`read_msg` returning `Err`. Useful for salvaging a
+    /// partially-corrupt capture.
+    pub fn with_lossy(mut self, lossy: bool) -> Self {
+        self.lossy = lossy;
+        self
+    }
+
     pub fn read_msg(&mut self, s: &mut String) -> Result<usize, DogStatsDReplayReaderError> {
         if let Some(line) = self.current_messages.pop_front() {
             s.insert_str(0, &line);
             return Ok(1);
         }
 
+        match self.read_record()? {
+            Some(record) => {
+                self.current_messages.extend(record.lines);
+                self.read_msg(s)
+            }
+            None => Ok(0), // Read was validly issued, just nothing to be read.
+        }
+    }
+
+    /// Reads the next `dogstatsd.unix` message and returns it as a
+    /// [`ReplayRecord`], preserving the original `timestamp`, `pid`, and
+    /// out-of-band `ancillary` bytes alongside the payload split into lines.
+    ///
+    /// Unlike `read_msg`, this does not buffer lines internally: every call
+    /// either advances to the next message or returns `Ok(None)` once the
+    /// replay is exhausted.
+    pub fn read_record(&mut self) -> Result<Option<ReplayRecord>, DogStatsDReplayReaderError> {
         match self.replay_msg_reader.read_msg() {
             Some(msg) => {
-                match std::str::from_utf8(&msg.payload) {
-                    Ok(v) => {
-                        if v.is_empty() {
-                            // Read operation was successful, read 0 msgs
-                            return Ok(0);
-                        }
-
-                        for line in v.lines() {
-                            self.current_messages.push_back(String::from(line));
-                        }
-
-                        self.read_msg(s)
+                let frame_index = self.frame_index;
+                self.frame_index += 1;
+
+                let lines: Vec<String> = match std::str::from_utf8(&msg.payload) {
+                    Ok(v) => v.lines().map(String::from).collect(),
+                    Err(_) if self.lossy => String::from_utf8_lossy(&msg.payload)
+                        .lines()
+                        .map(String::from)
+                        .collect(),
+                    Err(e) => {
+                        return Err(DogStatsDReplayReaderError::InvalidUtf8Sequence {
+                            payload: msg.payload,
+                            valid_up_to: e.valid_up_to(),
+                            frame_index,
+                        })
                     }
-                    Err(e) => Err(DogStatsDReplayReaderError::InvalidUtf8Sequence), // TODO add the msg or msg.payload that has the issue
+                };
+
+                if lines.is_empty() {
+                    return self.read_record();
                 }
+
+                Ok(Some(ReplayRecord {
+                    timestamp: msg.timestamp,
+                    pid: msg.pid,
+                    ancillary: msg.ancillary,
+                    lines,
+                }))
             }
-            None => Ok(0), // Read was validly issued, just nothing to be read.
+            None => Ok(None),
         }
     }
 
@@ -60,6 +131,9 @@ impl DogStatsDReplayReader {
             Ok(reader) => Ok(DogStatsDReplayReader {
                 replay_msg_reader: reader,
                 current_messages: VecDeque::new(),
+                frame_index: 0,
+                lossy: false,
+                errored: false,
             }),
             Err(e) => match e {
                 ReplayReaderError::NotAReplayFile => {
@@ -73,6 +147,30 @@ impl DogStatsDReplayReader {
     }
 }
 
+/// Iterates over the individual metric lines of a replay, in the same order
+/// `read_msg` would yield them. Once a message fails to decode as UTF-8, the
+/// iterator yields that one `Err` and then fuses: every subsequent call to
+/// `next` returns `None`, regardless of whether more frames remain.
+impl Iterator for DogStatsDReplayReader {
+    type Item = Result<String, DogStatsDReplayReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.errored {
+            return None;
+        }
+
+        let mut s = String::new();
+        match self.read_msg(&mut s) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(s)),
+            Err(e) => {
+                self.errored = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,6 +235,40 @@ mod tests {
         0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ];
 
+    const ONE_MSG_INVALID_UTF8: &[u8] = &[
+        0xd4, 0x74, 0xd0, 0x60, 0xf3, 0xff, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x08, 0x84, 0xe2,
+        0x88, 0x8a, 0xe0, 0xb6, 0x87, 0xbf, 0x17, 0x10, 0x83, 0x01, 0x1a, 0x04, 0x73, 0x74, 0xff,
+        0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn invalid_utf8_carries_payload_offset_and_frame_index() {
+        let mut replay = DogStatsDReplayReader::new(Bytes::from(ONE_MSG_INVALID_UTF8)).unwrap();
+
+        let err = replay.read_record().unwrap_err();
+        match err {
+            DogStatsDReplayReaderError::InvalidUtf8Sequence {
+                payload,
+                valid_up_to,
+                frame_index,
+            } => {
+                assert_eq!(&payload[..], &[0x73, 0x74, 0xff, 0x64]);
+                assert_eq!(valid_up_to, 2);
+                assert_eq!(frame_index, 0);
+            }
+            other => panic!("expected InvalidUtf8Sequence, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn lossy_mode_substitutes_replacement_characters() {
+        let replay = DogStatsDReplayReader::new(Bytes::from(ONE_MSG_INVALID_UTF8)).unwrap();
+        let mut replay = replay.with_lossy(true);
+
+        let record = replay.read_record().unwrap().unwrap();
+        assert_eq!(record.lines, vec!["st\u{fffd}d".to_string()]);
+    }
+
     #[test]
     fn two_msg_two_lines() {
         let mut replay = DogStatsDReplayReader::new(Bytes::from(TWO_MSGS_ONE_LINE_EACH)).unwrap();
@@ -152,6 +284,60 @@ mod tests {
         assert_eq!(res, 0);
     }
 
+    #[test]
+    fn two_msg_two_lines_read_record() {
+        let mut replay = DogStatsDReplayReader::new(Bytes::from(TWO_MSGS_ONE_LINE_EACH)).unwrap();
+
+        let record = replay.read_record().unwrap().unwrap();
+        assert_eq!(record.timestamp, 1692823177480253700);
+        assert_eq!(record.pid, 131);
+        assert!(record.ancillary.is_empty());
+        assert_eq!(record.lines, vec!["statsd.example.time.micros:2.39283|d|@1.000000|#environment:dev|c:2a25f7fc8fbf573d62053d7263dd2d440c07b6ab4d2b107e50b0d4df1f2ee15f".to_string()]);
+
+        let record = replay.read_record().unwrap().unwrap();
+        assert_eq!(record.timestamp, 1692823178271749279);
+        assert_eq!(record.pid, 131);
+
+        assert!(replay.read_record().unwrap().is_none());
+    }
+
+    #[test]
+    fn iterator_yields_all_lines() {
+        let replay = DogStatsDReplayReader::new(Bytes::from(ONE_MSG_THREE_LINES)).unwrap();
+        let lines: Result<Vec<String>, _> = replay.collect();
+        assert_eq!(
+            lines.unwrap(),
+            vec![
+                "statsd.other.metric:3|c|@1.000000|#environment:dev".to_string(),
+                "statsd.other.metric:8|c|@1.000000|#environment:dev".to_string(),
+                "statsd.other.metric:7|c|@1.000000|#environment:dev".to_string(),
+            ]
+        );
+    }
+
+    const INVALID_UTF8_THEN_VALID_MSG: &[u8] = &[
+        0xd4, 0x74, 0xd0, 0x60, 0xf3, 0xff, 0x00, 0x00, 0x13, 0x00, 0x00, 0x00, 0x08, 0x84, 0xe2,
+        0x88, 0x8a, 0xe0, 0xb6, 0x87, 0xbf, 0x17, 0x10, 0x83, 0x01, 0x1a, 0x04, 0x73, 0x74, 0xff,
+        0x64, 0x1a, 0x00, 0x00, 0x00, 0x08, 0x9f, 0xe9, 0xbd, 0x83, 0xe3, 0xb6, 0x87, 0xbf, 0x17,
+        0x10, 0x83, 0x01, 0x1a, 0x0b, 0x66, 0x6f, 0x6f, 0x2e, 0x62, 0x61, 0x72, 0x3a, 0x31, 0x7c,
+        0x63, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn iterator_fuses_after_a_decode_error() {
+        let mut replay =
+            DogStatsDReplayReader::new(Bytes::from(INVALID_UTF8_THEN_VALID_MSG)).unwrap();
+
+        assert!(matches!(
+            replay.next(),
+            Some(Err(DogStatsDReplayReaderError::InvalidUtf8Sequence { .. }))
+        ));
+        // The second, perfectly valid message is never surfaced: once the
+        // iterator has yielded an error it stays exhausted.
+        assert!(replay.next().is_none());
+        assert!(replay.next().is_none());
+    }
+
     #[test]
     fn one_msg_two_lines() {
         let mut replay = DogStatsDReplayReader::new(Bytes::from(ONE_MSG_TWO_LINES)).unwrap();
@@ -0,0 +1,84 @@
+use std::io::{self, Write};
+
+use bytes::{Bytes, BytesMut};
+use prost::Message;
+use thiserror::Error;
+
+use crate::dogstatsdreplayreader::dogstatsd::unix::UnixDogstatsdMsg;
+use crate::dogstatsdreplayreader::REPLAY_HEADER;
+
+/// 8 zero bytes mark the end of a replay, mirroring the trailer produced by
+/// the real dogstatsd-replay capture format.
+const REPLAY_TRAILER: [u8; 8] = [0; 8];
+
+#[derive(Error, Debug)]
+pub enum DogStatsDReplayWriterError {
+    #[error("failed to write replay: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Builds a `.bin` replay capture out of raw DogStatsD lines, the inverse of
+/// [`DogStatsDReplayReader`](crate::dogstatsdreplayreader::DogStatsDReplayReader).
+/// Useful for hand-crafting deterministic replay fixtures instead of
+/// embedding hand-assembled byte arrays in tests.
+pub struct DogStatsDReplayWriter<W> {
+    out: W,
+    header_written: bool,
+}
+
+impl<W> DogStatsDReplayWriter<W>
+where
+    W: Write,
+{
+    pub fn new(out: W) -> Self {
+        DogStatsDReplayWriter {
+            out,
+            header_written: false,
+        }
+    }
+
+    fn write_header_if_needed(&mut self) -> Result<(), DogStatsDReplayWriterError> {
+        if !self.header_written {
+            self.out.write_all(&REPLAY_HEADER)?;
+            self.header_written = true;
+        }
+        Ok(())
+    }
+
+    /// Wraps `line` into a `dogstatsd.unix` message stamped with `timestamp`
+    /// and `pid`, then appends it to the replay as a length-delimited frame.
+    pub fn write_line(
+        &mut self,
+        line: &str,
+        timestamp: i64,
+        pid: i32,
+    ) -> Result<(), DogStatsDReplayWriterError> {
+        self.write_header_if_needed()?;
+
+        let msg = UnixDogstatsdMsg {
+            timestamp,
+            pid,
+            payload: Bytes::copy_from_slice(line.as_bytes()),
+            ancillary: Bytes::new(),
+        };
+
+        let mut frame = BytesMut::with_capacity(msg.encoded_len());
+        msg.encode(&mut frame)
+            .expect("encoding a UnixDogstatsdMsg into a BytesMut cannot fail");
+
+        self.out.write_all(&(frame.len() as u32).to_le_bytes())?;
+        self.out.write_all(&frame)?;
+
+        Ok(())
+    }
+
+    /// Writes the replay trailer and flushes the underlying writer. Must be
+    /// called once all lines have been written, or the capture will be
+    /// missing its end-of-replay marker.
+    pub fn finish(mut self) -> Result<(), DogStatsDReplayWriterError> {
+        self.write_header_if_needed()?;
+        self.out.write_all(&REPLAY_TRAILER)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
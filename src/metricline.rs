@@ -0,0 +1,110 @@
+/// The DogStatsD metric type marker that appears after the `|` following a
+/// metric's value, e.g. the `c` in `page.views:1|c`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetricType {
+    Counter,
+    Gauge,
+    Histogram,
+    Distribution,
+    Set,
+    Timer,
+}
+
+impl MetricType {
+    fn parse(s: &str) -> Option<MetricType> {
+        match s {
+            "c" => Some(MetricType::Counter),
+            "g" => Some(MetricType::Gauge),
+            "h" => Some(MetricType::Histogram),
+            "d" => Some(MetricType::Distribution),
+            "s" => Some(MetricType::Set),
+            "ms" => Some(MetricType::Timer),
+            _ => None,
+        }
+    }
+}
+
+/// A single `name:value|type|@rate|#tags` metric line, decomposed into its
+/// fields. Sample rate and tags are optional per the DogStatsD protocol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedMetricLine {
+    pub name: String,
+    pub metric_type: MetricType,
+    pub sample_rate: Option<f64>,
+    pub tags: Vec<(String, Option<String>)>,
+}
+
+/// Parses a single metric line, returning `None` for anything that doesn't
+/// look like a well-formed DogStatsD metric (e.g. an event or service check
+/// line, or a line with an unrecognized type marker).
+pub fn parse_line(line: &str) -> Option<ParsedMetricLine> {
+    let mut fields = line.split('|');
+
+    let name_and_value = fields.next()?;
+    let (name, _value) = name_and_value.split_once(':')?;
+
+    let metric_type = MetricType::parse(fields.next()?)?;
+
+    let mut sample_rate = None;
+    let mut tags = Vec::new();
+
+    for field in fields {
+        if let Some(rate) = field.strip_prefix('@') {
+            sample_rate = rate.parse::<f64>().ok();
+        } else if let Some(tag_list) = field.strip_prefix('#') {
+            for tag in tag_list.split(',') {
+                match tag.split_once(':') {
+                    Some((key, value)) => tags.push((key.to_string(), Some(value.to_string()))),
+                    None => tags.push((tag.to_string(), None)),
+                }
+            }
+        }
+    }
+
+    Some(ParsedMetricLine {
+        name: name.to_string(),
+        metric_type,
+        sample_rate,
+        tags,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_counter_with_rate_and_tags() {
+        let parsed = parse_line("page.views:1|c|@0.5|#env:prod,team:core").unwrap();
+        assert_eq!(parsed.name, "page.views");
+        assert_eq!(parsed.metric_type, MetricType::Counter);
+        assert_eq!(parsed.sample_rate, Some(0.5));
+        assert_eq!(
+            parsed.tags,
+            vec![
+                ("env".to_string(), Some("prod".to_string())),
+                ("team".to_string(), Some("core".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_distribution_without_tags() {
+        let parsed = parse_line("statsd.example.time.micros:2.39283|d|@1.000000").unwrap();
+        assert_eq!(parsed.name, "statsd.example.time.micros");
+        assert_eq!(parsed.metric_type, MetricType::Distribution);
+        assert_eq!(parsed.sample_rate, Some(1.0));
+        assert!(parsed.tags.is_empty());
+    }
+
+    #[test]
+    fn rejects_unrecognized_type() {
+        assert!(parse_line("page.views:1|e").is_none());
+    }
+
+    #[test]
+    fn parses_tag_without_value() {
+        let parsed = parse_line("jobs.run:1|c|#urgent").unwrap();
+        assert_eq!(parsed.tags, vec![("urgent".to_string(), None)]);
+    }
+}